@@ -0,0 +1,189 @@
+// Type-erased command queue, for mixing several concrete command types in a
+// single FIFO without boxing every element. Payloads are packed into a byte
+// arena and a parallel metadata vec remembers how to read and apply each one.
+use std::mem::MaybeUninit;
+use std::ptr;
+
+/// A command that can be applied against some `Target`, for use with
+/// [`AnyCommandBuffer`]. Implement this for each concrete command type you
+/// want to mix into a single buffer.
+pub trait Command<Target>: Sized {
+    fn apply(self, target: &mut Target);
+}
+
+/// Describes one packed command: where its bytes start in the arena, and the
+/// monomorphized function pointer that knows how to read and apply them.
+struct CommandMeta<Target> {
+    offset: usize,
+    apply: unsafe fn(*mut MaybeUninit<u8>, &mut Target),
+    drop: unsafe fn(*mut MaybeUninit<u8>),
+}
+
+/// A command queue that can hold several different concrete [`Command`]
+/// types at once, applying each against a `Target` in FIFO order without
+/// boxing.
+///
+/// Commands are packed into a single byte arena (like a small bump
+/// allocator) rather than stored behind `Box<dyn Command>`, so pushing a
+/// command only costs a memcpy into the arena plus one small metadata entry.
+pub struct AnyCommandBuffer<Target> {
+    bytes: Vec<MaybeUninit<u8>>,
+    metas: Vec<CommandMeta<Target>>,
+}
+
+impl<Target> AnyCommandBuffer<Target> {
+    pub fn new() -> Self {
+        Self {
+            bytes: Vec::new(),
+            metas: Vec::new(),
+        }
+    }
+
+    /// Enqueues a command of any type implementing `Command<Target>`.
+    pub fn push<C: Command<Target> + 'static>(&mut self, command: C) {
+        let align = std::mem::align_of::<C>();
+        let size = std::mem::size_of::<C>();
+
+        let offset = align_up(self.bytes.len(), align);
+        self.bytes.resize_with(offset + size, MaybeUninit::uninit);
+
+        unsafe {
+            let dst = self.bytes.as_mut_ptr().add(offset) as *mut C;
+            ptr::write_unaligned(dst, command);
+        }
+
+        unsafe fn apply<Target, C: Command<Target>>(ptr: *mut MaybeUninit<u8>, target: &mut Target) {
+            let value = ptr::read_unaligned(ptr as *const C);
+            value.apply(target);
+        }
+        // The arena only pads offsets to `align_of::<C>()` relative to the
+        // start of `bytes`, not to the allocator's actual base address, so
+        // `ptr` isn't guaranteed properly aligned for `C`. Move the value
+        // out with an unaligned read (as `apply` does) and let it drop
+        // normally on the stack, rather than calling `drop_in_place` on the
+        // arena pointer directly.
+        unsafe fn drop_unapplied<C>(ptr: *mut MaybeUninit<u8>) {
+            let _ = ptr::read_unaligned(ptr as *const C);
+        }
+
+        self.metas.push(CommandMeta {
+            offset,
+            apply: apply::<Target, C>,
+            drop: drop_unapplied::<C>,
+        });
+    }
+
+    /// Applies every queued command against `target` in FIFO order, then
+    /// clears the buffer.
+    pub fn apply_all(&mut self, target: &mut Target) {
+        for meta in self.metas.drain(..) {
+            let ptr = unsafe { self.bytes.as_mut_ptr().add(meta.offset) };
+            unsafe { (meta.apply)(ptr, target) };
+        }
+        self.bytes.clear();
+    }
+
+    /// Returns the number of commands currently queued.
+    pub fn len(&self) -> usize {
+        self.metas.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metas.is_empty()
+    }
+
+    /// Drops every queued command without applying it.
+    pub fn clear(&mut self) {
+        for meta in self.metas.drain(..) {
+            let ptr = unsafe { self.bytes.as_mut_ptr().add(meta.offset) };
+            unsafe { (meta.drop)(ptr) };
+        }
+        self.bytes.clear();
+    }
+}
+
+impl<Target> Default for AnyCommandBuffer<Target> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Target> Drop for AnyCommandBuffer<Target> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Target {
+        log: Vec<String>,
+    }
+
+    struct Spawn(&'static str);
+    impl Command<Target> for Spawn {
+        fn apply(self, target: &mut Target) {
+            target.log.push(format!("spawn {}", self.0));
+        }
+    }
+
+    struct Damage(u32);
+    impl Command<Target> for Damage {
+        fn apply(self, target: &mut Target) {
+            target.log.push(format!("damage {}", self.0));
+        }
+    }
+
+    #[test]
+    fn test_mixed_types_apply_in_order() {
+        let mut buf = AnyCommandBuffer::<Target>::new();
+        buf.push(Spawn("enemy"));
+        buf.push(Damage(5));
+        buf.push(Spawn("boss"));
+        assert_eq!(buf.len(), 3);
+
+        let mut target = Target { log: Vec::new() };
+        buf.apply_all(&mut target);
+        assert!(buf.is_empty());
+        assert_eq!(
+            target.log,
+            vec!["spawn enemy", "damage 5", "spawn boss"]
+        );
+    }
+
+    #[test]
+    fn test_clear_drops_without_applying() {
+        let mut buf = AnyCommandBuffer::<Target>::new();
+        buf.push(Spawn("enemy"));
+        buf.push(Damage(5));
+        buf.clear();
+        assert!(buf.is_empty());
+
+        let mut target = Target { log: Vec::new() };
+        buf.apply_all(&mut target);
+        assert!(target.log.is_empty());
+    }
+
+    #[repr(align(64))]
+    struct OverAligned(u64);
+    impl Command<Target> for OverAligned {
+        fn apply(self, target: &mut Target) {
+            target.log.push(format!("over-aligned {}", self.0));
+        }
+    }
+
+    #[test]
+    fn test_clear_drops_overaligned_command_without_ub() {
+        let mut buf = AnyCommandBuffer::<Target>::new();
+        buf.push(Spawn("enemy"));
+        buf.push(OverAligned(7));
+        buf.clear();
+        assert!(buf.is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+// Producer/consumer split of the command buffer, for driving an async input
+// loop (sockets, stdin, etc.) instead of busy-polling `read_command`.
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::pin::Pin;
+use std::rc::{Rc, Weak};
+use std::task::{Context, Poll, Waker};
+
+use futures::Stream;
+
+/// Status of the channel as observed by a producer, mirroring the overrun
+/// behavior of [`crate::CommandBuffer`] but surfaced so producers can choose
+/// to pause instead of silently overwriting the oldest command.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PayloadStatus {
+    /// The buffer has room; writes will not drop anything.
+    Read,
+    /// The buffer is at capacity; the next write will drop the oldest command.
+    Pause,
+    /// The receiver has been dropped; writes are discarded.
+    Dropped,
+}
+
+struct Inner<T> {
+    events: VecDeque<T>,
+    capacity: usize,
+    waker: Option<Waker>,
+    senders: usize,
+}
+
+/// The producer half of a [`channel`]. Cloneable; any clone can write.
+pub struct CommandSender<T> {
+    inner: Weak<RefCell<Inner<T>>>,
+}
+
+/// The consumer half of a [`channel`]. Implements [`Stream`], yielding
+/// commands as they are written and finishing (`Ready(None)`) once every
+/// [`CommandSender`] has been dropped and the buffer has drained.
+pub struct CommandReceiver<T> {
+    inner: Rc<RefCell<Inner<T>>>,
+}
+
+/// Creates a linked [`CommandSender`]/[`CommandReceiver`] pair sharing a
+/// buffer of `capacity`, with the same drop-oldest overrun behavior as
+/// [`crate::CommandBuffer`].
+pub fn channel<T>(capacity: usize) -> (CommandSender<T>, CommandReceiver<T>) {
+    let inner = Rc::new(RefCell::new(Inner {
+        events: VecDeque::with_capacity(capacity),
+        capacity,
+        waker: None,
+        senders: 1,
+    }));
+    (
+        CommandSender {
+            inner: Rc::downgrade(&inner),
+        },
+        CommandReceiver { inner },
+    )
+}
+
+impl<T> CommandSender<T> {
+    /// Writes a command to the buffer and wakes the receiver if it is
+    /// parked. Returns true if the buffer overran and the oldest command was
+    /// dropped, and false otherwise, including when the write was discarded
+    /// because the receiver is gone.
+    pub fn write_command(&self, event: T) -> bool {
+        let Some(inner) = self.inner.upgrade() else {
+            return false;
+        };
+        let mut inner = inner.borrow_mut();
+        let capped = if inner.events.len() >= inner.capacity {
+            inner.events.pop_front();
+            true
+        } else {
+            false
+        };
+        inner.events.push_back(event);
+        if let Some(waker) = inner.waker.take() {
+            waker.wake();
+        }
+        capped
+    }
+
+    /// Reports whether a write would overrun the buffer, or if the receiver
+    /// has gone away entirely.
+    pub fn status(&self) -> PayloadStatus {
+        let Some(inner) = self.inner.upgrade() else {
+            return PayloadStatus::Dropped;
+        };
+        let inner = inner.borrow();
+        if inner.events.len() >= inner.capacity {
+            PayloadStatus::Pause
+        } else {
+            PayloadStatus::Read
+        }
+    }
+}
+
+impl<T> Clone for CommandSender<T> {
+    fn clone(&self) -> Self {
+        if let Some(inner) = self.inner.upgrade() {
+            inner.borrow_mut().senders += 1;
+        }
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T> Drop for CommandSender<T> {
+    fn drop(&mut self) {
+        let Some(inner) = self.inner.upgrade() else {
+            return;
+        };
+        let mut inner = inner.borrow_mut();
+        inner.senders -= 1;
+        if inner.senders == 0 {
+            if let Some(waker) = inner.waker.take() {
+                waker.wake();
+            }
+        }
+    }
+}
+
+impl<T> Stream for CommandReceiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut inner = self.inner.borrow_mut();
+        if let Some(event) = inner.events.pop_front() {
+            Poll::Ready(Some(event))
+        } else if inner.senders == 0 {
+            Poll::Ready(None)
+        } else {
+            inner.waker = Some(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<T> CommandReceiver<T> {
+    /// Returns a new sender for this receiver's buffer.
+    pub fn sender(&self) -> CommandSender<T> {
+        self.inner.borrow_mut().senders += 1;
+        CommandSender {
+            inner: Rc::downgrade(&self.inner),
+        }
+    }
+
+    /// Returns the number of commands currently buffered.
+    pub fn len(&self) -> usize {
+        self.inner.borrow().events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use futures::task::noop_waker;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::task::Wake;
+
+    struct FlagWaker(AtomicBool);
+
+    impl Wake for FlagWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+        fn wake_by_ref(self: &Arc<Self>) {
+            self.0.store(true, Ordering::SeqCst);
+        }
+    }
+
+    #[test]
+    fn test_status_reports_backpressure() {
+        let (tx, rx) = channel::<u32>(2);
+        assert_eq!(tx.status(), PayloadStatus::Read);
+        tx.write_command(1);
+        tx.write_command(2);
+        assert_eq!(tx.status(), PayloadStatus::Pause);
+        assert!(tx.write_command(3));
+        assert_eq!(rx.len(), 2);
+    }
+
+    #[test]
+    fn test_status_dropped_once_receiver_gone() {
+        let (tx, rx) = channel::<u32>(2);
+        drop(rx);
+        assert_eq!(tx.status(), PayloadStatus::Dropped);
+        assert!(!tx.write_command(1));
+    }
+
+    #[test]
+    fn test_poll_next_wakes_on_write() {
+        let (tx, mut rx) = channel::<u32>(2);
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+        assert!(!flag.0.load(Ordering::SeqCst));
+
+        tx.write_command(42);
+        assert!(flag.0.load(Ordering::SeqCst));
+
+        let noop = noop_waker();
+        let mut cx = Context::from_waker(&noop);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(Some(42)));
+    }
+
+    #[test]
+    fn test_poll_next_ends_stream_once_all_senders_dropped() {
+        let (tx, mut rx) = channel::<u32>(2);
+        let noop = noop_waker();
+        let mut cx = Context::from_waker(&noop);
+
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+        drop(tx);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn test_poll_next_wakes_when_last_sender_drops() {
+        let (tx, mut rx) = channel::<u32>(2);
+        let flag = Arc::new(FlagWaker(AtomicBool::new(false)));
+        let waker = Waker::from(flag.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Pending);
+        assert!(!flag.0.load(Ordering::SeqCst));
+
+        drop(tx);
+        assert!(flag.0.load(Ordering::SeqCst));
+
+        let noop = noop_waker();
+        let mut cx = Context::from_waker(&noop);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
+    }
+
+    #[test]
+    fn test_poll_next_drains_before_ending_with_clones() {
+        let (tx, mut rx) = channel::<u32>(2);
+        let tx2 = tx.clone();
+        tx.write_command(1);
+        drop(tx);
+        drop(tx2);
+
+        let noop = noop_waker();
+        let mut cx = Context::from_waker(&noop);
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(Some(1)));
+        assert_eq!(Pin::new(&mut rx).poll_next(&mut cx), Poll::Ready(None));
+    }
+}
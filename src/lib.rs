@@ -1,4 +1,42 @@
+//! `CommandBuffer`'s FIFO/overrun/`retain` core only ever touches
+//! `alloc::collections::VecDeque` and `core::fmt::Debug`, so it works without
+//! `std` too. Everything else in this crate (the registry's `HashMap`, the
+//! channel's async `Stream`, ...) pulls in `std`, so those stay behind the
+//! default `std` feature.
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
 use std::{collections::VecDeque, fmt::Debug};
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::VecDeque;
+#[cfg(not(feature = "std"))]
+use core::fmt::Debug;
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+#[cfg(feature = "std")]
+mod any;
+#[cfg(feature = "std")]
+pub use any::{AnyCommandBuffer, Command};
+
+#[cfg(feature = "std")]
+mod channel;
+#[cfg(feature = "std")]
+pub use channel::{channel, CommandReceiver, CommandSender, PayloadStatus};
+
+#[cfg(feature = "std")]
+mod registry;
+#[cfg(feature = "std")]
+pub use registry::{CommandRegistry, ParseError};
+
+#[cfg(feature = "std")]
+mod timed;
+#[cfg(feature = "std")]
+pub use timed::{Tick, TimedCommandBuffer};
+
 // Commands that act as the "input" to the game engine.
 // FIFO buffer implementation
 // Push: A, B, C, D
@@ -61,6 +99,18 @@ where
         self.events.is_empty()
     }
 
+    /// Parses `line` against `registry` and, on success, enqueues the result
+    /// in one step. Returns the same overrun signal as `write_command`.
+    #[cfg(feature = "std")]
+    pub fn write_line(
+        &mut self,
+        registry: &registry::CommandRegistry<T>,
+        line: &str,
+    ) -> Result<bool, registry::ParseError> {
+        let command = registry.parse_line(line)?;
+        Ok(self.write_command(command))
+    }
+
     /// Retains elements that do not match the predicate and pops off elements that match.
     /// Returns a vector of popped elements.
     pub fn retain<F>(&mut self, mut predicate: F) -> Vec<T>
@@ -86,7 +136,7 @@ impl<T> Debug for CommandBuffer<T>
 where
     T: Debug + Sync + Send,
 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("CommandBuffer")
             .field("events", &self.events)
             .finish()
@@ -96,6 +146,8 @@ where
 #[cfg(test)]
 mod test {
     use super::*;
+    #[cfg(not(feature = "std"))]
+    use alloc::vec;
 
     #[derive(Debug, Copy, Clone, PartialEq, Eq)]
     enum Event {
@@ -120,7 +172,7 @@ mod test {
         assert_eq!(events.read_command(), Some(Event::Left));
         assert_eq!(events.read_command(), Some(Event::Right));
         assert_eq!(events.read_command(), None);
-        assert!(events.len() == 0);
+        assert!(events.is_empty());
     }
 
     #[test]
@@ -1,4 +1,4 @@
-use eventsys::CommandBuffer;
+use eventsys::{CommandBuffer, CommandRegistry};
 
 struct Renderer;
 
@@ -23,7 +23,7 @@ struct State {
 
 impl State {
     fn dispatch(&mut self, buf: &mut CommandBuffer<Command>) {
-        while buf.len() > 0 {
+        while !buf.is_empty() {
             match buf.read_command().unwrap() {
                 Command::Stuff => self.world.do_stuff(),
                 Command::Things => self.renderer.do_things(),
@@ -40,24 +40,37 @@ enum Command {
     Exit,
 }
 
+/// Maps input line names to `Command` variants, replacing the hardcoded
+/// `match` the REPL used to dispatch on literal strings.
+fn registry() -> CommandRegistry<Command> {
+    let mut registry = CommandRegistry::new();
+    registry.register("stuff", |_args| Ok(Command::Stuff));
+    registry.register("things", |_args| Ok(Command::Things));
+    registry.register("exit", |_args| Ok(Command::Exit));
+    registry
+}
+
 fn main() {
     let stdin = std::io::stdin();
     let mut input_buf = String::new();
     let mut command_buf = CommandBuffer::new(5);
+    let registry = registry();
 
     let mut state = State {
-        renderer: Renderer{},
-        world: World{}
+        renderer: Renderer {},
+        world: World {},
     };
     loop {
         stdin.read_line(&mut input_buf).unwrap();
-        match input_buf.as_str().trim() {
-            "stuff" => { command_buf.write_command(Command::Stuff); },
-            "things" => { command_buf.write_command(Command::Things); },
-            "exit" => { command_buf.write_command(Command::Exit); },
-            "dispatch" => { state.dispatch(&mut command_buf); },
-            _ => panic!("unrecognized command {}", input_buf.as_str()),
-        };
+        let line = input_buf.trim();
+        if line == "dispatch" {
+            state.dispatch(&mut command_buf);
+        } else {
+            match command_buf.write_line(&registry, line) {
+                Ok(_) => {}
+                Err(err) => panic!("{}", err),
+            }
+        }
         input_buf.clear();
     }
 }
@@ -0,0 +1,157 @@
+// Text-to-command parser registry, so a `CommandBuffer` can be driven from a
+// debug console / REPL instead of a hardcoded `match` over literal strings.
+use std::collections::HashMap;
+use std::fmt;
+
+/// Why a line failed to parse into a command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The first token didn't match any registered command name.
+    UnknownCommand(String),
+    /// A registered parser rejected the argument count.
+    WrongArity { command: String, got: usize },
+    /// A registered parser rejected one of the arguments.
+    BadArgument { command: String, token: String },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseError::UnknownCommand(token) => write!(f, "unknown command: {token}"),
+            ParseError::WrongArity { command, got } => {
+                write!(f, "wrong number of arguments for {command}: got {got}")
+            }
+            ParseError::BadArgument { command, token } => {
+                write!(f, "bad argument for {command}: {token}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+type ParserFn<T> = Box<dyn Fn(&[&str]) -> Result<T, ParseError> + Sync + Send>;
+
+/// A registry of named commands mapped to parser functions, used to turn a
+/// line of text (e.g. `"spawn enemy 3"`) into a concrete `T`.
+pub struct CommandRegistry<T> {
+    parsers: HashMap<String, ParserFn<T>>,
+}
+
+impl<T> CommandRegistry<T> {
+    pub fn new() -> Self {
+        Self {
+            parsers: HashMap::new(),
+        }
+    }
+
+    /// Registers a parser for commands named `name`. The parser receives the
+    /// tokens following the command name and must produce a `T` or a
+    /// [`ParseError`] describing why it couldn't.
+    pub fn register<F>(&mut self, name: &str, parser: F)
+    where
+        F: Fn(&[&str]) -> Result<T, ParseError> + Sync + Send + 'static,
+    {
+        self.parsers.insert(name.to_string(), Box::new(parser));
+    }
+
+    /// Tokenizes `line` on whitespace, looks up the first token as a command
+    /// name, and passes the rest as arguments to its parser.
+    pub fn parse_line(&self, line: &str) -> Result<T, ParseError> {
+        let mut tokens = line.split_whitespace();
+        let name = tokens
+            .next()
+            .ok_or_else(|| ParseError::UnknownCommand(String::new()))?;
+        let parser = self
+            .parsers
+            .get(name)
+            .ok_or_else(|| ParseError::UnknownCommand(name.to_string()))?;
+        let args: Vec<&str> = tokens.collect();
+        parser(&args)
+    }
+}
+
+impl<T> Default for CommandRegistry<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::CommandBuffer;
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum Command {
+        Spawn { kind: String, count: u32 },
+    }
+
+    fn registry() -> CommandRegistry<Command> {
+        let mut registry = CommandRegistry::new();
+        registry.register("spawn", |args| match args {
+            [kind, count] => {
+                let count = count.parse::<u32>().map_err(|_| ParseError::BadArgument {
+                    command: "spawn".to_string(),
+                    token: count.to_string(),
+                })?;
+                Ok(Command::Spawn {
+                    kind: kind.to_string(),
+                    count,
+                })
+            }
+            _ => Err(ParseError::WrongArity {
+                command: "spawn".to_string(),
+                got: args.len(),
+            }),
+        });
+        registry
+    }
+
+    #[test]
+    fn test_parse_line() {
+        let registry = registry();
+        assert_eq!(
+            registry.parse_line("spawn enemy 3").unwrap(),
+            Command::Spawn {
+                kind: "enemy".to_string(),
+                count: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_command() {
+        let registry = registry();
+        assert_eq!(
+            registry.parse_line("nonsense"),
+            Err(ParseError::UnknownCommand("nonsense".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_wrong_arity() {
+        let registry = registry();
+        assert_eq!(
+            registry.parse_line("spawn enemy"),
+            Err(ParseError::WrongArity {
+                command: "spawn".to_string(),
+                got: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_write_line() {
+        let registry = registry();
+        let mut buf = CommandBuffer::<Command>::new(5);
+        assert!(!buf.write_line(&registry, "spawn enemy 3").unwrap());
+        assert_eq!(
+            buf.read_command(),
+            Some(Command::Spawn {
+                kind: "enemy".to_string(),
+                count: 3,
+            })
+        );
+    }
+}
@@ -0,0 +1,134 @@
+// Opt-in timestamped wrapper around the command buffer, for recording input
+// and replaying it back frame-accurately. Kept as a separate type so the
+// plain `CommandBuffer<T>` stays zero-overhead for callers that don't need it.
+use std::collections::VecDeque;
+
+/// A monotonic point in time a command was written at. Typically a frame or
+/// tick counter, but any monotonically increasing value works (e.g. a
+/// `std::time::Instant` reduced to nanos since some epoch).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tick(pub u64);
+
+/// A command buffer that stamps every write with a [`Tick`], so a recording
+/// can be drained and replayed with its original relative timing preserved.
+pub struct TimedCommandBuffer<T>
+where
+    T: Sync + Send,
+{
+    events: VecDeque<(Tick, T)>,
+    capacity: usize,
+}
+
+impl<T> TimedCommandBuffer<T>
+where
+    T: Sync + Send,
+{
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            events: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    /// Stamps `event` with `tick` and writes it to the buffer. Returns true
+    /// if the buffer overran and the oldest command was dropped.
+    pub fn write_command(&mut self, tick: Tick, event: T) -> bool {
+        let capped = if self.events.len() >= self.capacity {
+            self.events.pop_front();
+            true
+        } else {
+            false
+        };
+        self.events.push_back((tick, event));
+        capped
+    }
+
+    /// Reads the next command only if its tick is `<= now`, leaving it
+    /// queued otherwise. Useful for scheduled/delayed inputs.
+    pub fn read_command_at(&mut self, now: Tick) -> Option<T> {
+        if self.events.front().map(|(tick, _)| *tick <= now) == Some(true) {
+            self.events.pop_front().map(|(_, event)| event)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the full ordered log of `(tick, command)` pairs currently
+    /// held, without consuming them.
+    pub fn drain_recording(&self) -> Vec<(Tick, T)>
+    where
+        T: Clone,
+    {
+        self.events.iter().cloned().collect()
+    }
+
+    /// Rebuilds a buffer from a recording, preserving each command's
+    /// original tick so it can be fed back through `read_command_at` with
+    /// the same relative timing. `capacity` governs writes made to the
+    /// returned buffer afterwards (e.g. live input mixed into the replay),
+    /// the same as `new`; it is widened to fit the recording if it's
+    /// smaller, so replaying never itself triggers an overrun.
+    pub fn replay<I: IntoIterator<Item = (Tick, T)>>(capacity: usize, recording: I) -> Self {
+        let events: VecDeque<(Tick, T)> = recording.into_iter().collect();
+        let capacity = capacity.max(events.len());
+        Self { events, capacity }
+    }
+
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.events.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_read_command_at_respects_tick() {
+        let mut buf = TimedCommandBuffer::<&'static str>::new(5);
+        buf.write_command(Tick(10), "jump");
+        buf.write_command(Tick(20), "shoot");
+
+        assert_eq!(buf.read_command_at(Tick(5)), None);
+        assert_eq!(buf.read_command_at(Tick(10)), Some("jump"));
+        assert_eq!(buf.read_command_at(Tick(15)), None);
+        assert_eq!(buf.read_command_at(Tick(20)), Some("shoot"));
+    }
+
+    #[test]
+    fn test_drain_and_replay() {
+        let mut buf = TimedCommandBuffer::<&'static str>::new(5);
+        buf.write_command(Tick(1), "a");
+        buf.write_command(Tick(2), "b");
+
+        let recording = buf.drain_recording();
+        let mut replayed = TimedCommandBuffer::replay(5, recording);
+
+        assert_eq!(replayed.read_command_at(Tick(1)), Some("a"));
+        assert_eq!(replayed.read_command_at(Tick(2)), Some("b"));
+        assert!(replayed.is_empty());
+    }
+
+    #[test]
+    fn test_write_command_after_replay_does_not_spuriously_overrun() {
+        let mut buf = TimedCommandBuffer::<&'static str>::new(5);
+        buf.write_command(Tick(1), "a");
+        buf.write_command(Tick(2), "b");
+        let recording = buf.drain_recording();
+
+        let mut replayed = TimedCommandBuffer::replay(5, recording);
+        assert!(!replayed.write_command(Tick(3), "c"));
+        assert_eq!(replayed.len(), 3);
+
+        let mut empty_replay = TimedCommandBuffer::<&'static str>::replay(5, Vec::new());
+        assert!(!empty_replay.write_command(Tick(1), "fresh"));
+    }
+}